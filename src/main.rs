@@ -1,5 +1,6 @@
 use std::error::Error;
 use serde_derive::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::fs::File;
 use std::io::Write;
@@ -9,7 +10,7 @@ use pulldown_cmark::{html, Options, Parser};
 use tera::{Context, Tera};
 use yaml_front_matter::YamlFrontMatter;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 struct PageInfo {
     title: String,
     description: String,
@@ -18,6 +19,42 @@ struct PageInfo {
     date: String,
     favorite_numbers: Vec<f64>,
     path: String,
+    #[serde(default)]
+    word_count: usize,
+    #[serde(default)]
+    reading_time: usize,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    slug: String,
+}
+
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Count words in a body of text, treating each CJK character as its own word
+/// since those scripts aren't whitespace-delimited.
+fn count_words(text: &str) -> usize {
+    let cjk = text.chars().filter(|c| is_cjk(*c)).count();
+    let latin = text
+        .split_whitespace()
+        .filter(|word| !word.chars().all(is_cjk))
+        .count();
+    cjk + latin
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0x3400..=0x4DBF // CJK Extension A
+        | 0x3040..=0x30FF // Hiragana + Katakana
+        | 0xAC00..=0xD7AF // Hangul syllables
+    )
+}
+
+/// Estimate reading time in minutes from a word count, clamped to at least one.
+fn reading_time(word_count: usize) -> usize {
+    let minutes = (word_count as f64 / WORDS_PER_MINUTE as f64).round() as usize;
+    minutes.max(1)
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,14 +75,51 @@ fn load_config() -> Result<SiteConfig, Box<dyn Error>> {
 
 fn read_dir(path: &str) -> std::io::Result<Vec<std::path::PathBuf>> {
     let mut files = Vec::new();
-    for entry in fs::read_dir(path)? {
+    collect_markdown_files(Path::new(path), &mut files)?;
+    Ok(files)
+}
+
+fn collect_markdown_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
-        if path.is_file() {
+        if path.is_dir() {
+            collect_markdown_files(&path, files)?;
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("md") {
             files.push(path);
         }
     }
-    Ok(files)
+    Ok(())
+}
+
+/// Drop a leading YAML front-matter block so excerpt extraction only sees the
+/// markdown body.
+fn strip_front_matter(content: &str) -> &str {
+    let trimmed = content.trim_start();
+    if let Some(rest) = trimmed.strip_prefix("---") {
+        if let Some(end) = rest.find("\n---") {
+            let after = &rest[end + "\n---".len()..];
+            // Skip the remainder of the closing fence line (any extra '-' plus
+            // its newline) without touching the body, so a leading bullet list
+            // or thematic break survives intact.
+            return match after.find('\n') {
+                Some(newline) => &after[newline + 1..],
+                None => "",
+            };
+        }
+    }
+    content
+}
+
+/// Render a page's summary: the text before a `<!-- more -->` marker when one is
+/// present, otherwise the first paragraph up to the first blank line.
+fn extract_summary(content: &str) -> String {
+    let body = strip_front_matter(content);
+    let excerpt = match body.split_once("<!-- more -->") {
+        Some((before, _)) => before,
+        None => body.split("\n\n").find(|para| !para.trim().is_empty()).unwrap_or(""),
+    };
+    parse_markdown(excerpt.trim())
 }
 
 fn parse_markdown(content: &str) -> String {
@@ -57,50 +131,291 @@ fn parse_markdown(content: &str) -> String {
     html_output
 }
 
-fn render_template(tera: &Tera, html_content: &str, title: &String, config: &SiteConfig) -> Result<String, tera::Error> {
+/// Collect every non-markdown sibling file in a page's directory so colocated
+/// images and downloads can be shipped alongside the rendered page.
+fn find_related_assets(dir: &Path) -> std::io::Result<Vec<std::path::PathBuf>> {
+    let mut assets = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_file() && path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+            assets.push(path);
+        }
+    }
+    Ok(assets)
+}
+
+fn render_template(tera: &Tera, html_content: &str, page: &PageInfo, prev: Option<&PageInfo>, next: Option<&PageInfo>, assets: &[String], config: &SiteConfig) -> Result<String, tera::Error> {
     let mut context = Context::new();
-    context.insert("post_title", title);
+    context.insert("post_title", &page.title);
     context.insert("site_title", &config.site_title);
     context.insert("content", html_content);
     context.insert("base_url", &config.base_url);
+    context.insert("word_count", &page.word_count);
+    context.insert("reading_time", &page.reading_time);
+    context.insert("assets", assets);
+
+    // Post navigation links are site-absolute (prefixed with `base_url`) so they
+    // resolve correctly from nested output paths, matching the taxonomy pages.
+    let base = config.base_url.trim_end_matches('/');
+    if let Some(prev) = prev {
+        context.insert("prev_title", &prev.title);
+        context.insert("prev_path", &format!("{}/{}", base, prev.path));
+    }
+    if let Some(next) = next {
+        context.insert("next_title", &next.title);
+        context.insert("next_path", &format!("{}/{}", base, next.path));
+    }
 
     tera.render("template.html", &context)
 }
 
+/// Normalize a front-matter `date` into a lexicographically sortable key. The
+/// date is only trimmed, not parsed: `YYYY-MM-DD` dates and RFC3339 timestamps
+/// sharing one UTC offset sort correctly as strings, while timestamps with
+/// different offsets are compared as text rather than as instants. Empty dates
+/// sort last under a newest-first ordering.
+fn sortable_date(date: &str) -> String {
+    date.trim().to_string()
+}
+
+/// Derive a `YYYY-MM-DD` date from a leading date prefix on the filename stem,
+/// e.g. `2024-01-02-my-post.md`.
+fn derive_date_from_filename(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    let bytes = stem.as_bytes();
+    if bytes.len() >= 11
+        && bytes[10] == b'-'
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && bytes[..10]
+            .iter()
+            .enumerate()
+            .all(|(i, b)| if i == 4 || i == 7 { *b == b'-' } else { b.is_ascii_digit() })
+    {
+        Some(stem[..10].to_string())
+    } else {
+        None
+    }
+}
+
 fn write_output_file(output_path: &Path, content: &str) -> std::io::Result<()> {
     let mut file = File::create(output_path)?;
     file.write_all(content.as_bytes())
 }
 
 fn generate_site(input_dir: &str, output_dir: &str, tera: &Tera, config: SiteConfig) -> std::io::Result<()>{
+    // Start from a clean slate so deleted or renamed sources don't leave stale
+    // HTML behind.
+    if Path::new(output_dir).exists() {
+        fs::remove_dir_all(output_dir)?;
+    }
     fs::create_dir_all(output_dir)?;
 
+    // Ship the theme's bundled static files (CSS/JS/etc.) with the site.
+    if !config.theme.trim().is_empty() {
+        let theme_static = Path::new("themes").join(&config.theme).join("static");
+        if theme_static.exists() {
+            copy_dir_all(&theme_static, Path::new(output_dir))?;
+        }
+    }
+
     let files = read_dir(input_dir)?;
-    let mut pages: Vec<PageInfo> = Vec::new();
+    let mut rendered: Vec<RenderedPage> = Vec::new();
+    // Track directories whose assets have already been copied so posts sharing a
+    // directory don't each re-copy the same siblings.
+    let mut copied_dirs: HashSet<std::path::PathBuf> = HashSet::new();
 
     for file_path in files {
         let content = fs::read_to_string(&file_path)?;
         let mut page_info = collect_metadata(&content)?;
+        page_info.word_count = count_words(strip_front_matter(&content));
+        page_info.reading_time = reading_time(page_info.word_count);
+        page_info.summary = extract_summary(&content);
+        if page_info.date.trim().is_empty() {
+            if let Some(date) = derive_date_from_filename(&file_path) {
+                page_info.date = date;
+            }
+        }
         let html_content = parse_markdown(&content);
-        let rendered_content = render_template(tera, &html_content, &page_info.title, &config).unwrap();
-        let output_file_path =
-            Path::new(output_dir)
-                .join(
-                    Path::new(
-                        Path::new(&file_path).file_stem().unwrap().to_str().unwrap()
-                    ).with_extension("html")
-                );
 
-        write_output_file(&output_file_path, &rendered_content)?;
-        page_info.path = output_file_path.file_name().unwrap().to_str().unwrap().to_string();
-        pages.push(page_info);
+        // Compute a stable slug for the output filename and link, preferring an
+        // explicit front-matter `slug` and otherwise slugifying the title.
+        let mut slug = if page_info.slug.trim().is_empty() {
+            slugify(&page_info.title)
+        } else {
+            slugify(&page_info.slug)
+        };
+
+        let source_relative = file_path.strip_prefix(input_dir).unwrap_or(&file_path);
+        let relative_path = match source_relative.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.join(format!("{}.html", slug)),
+            _ => {
+                // A root-level `index` slug would overwrite the generated home
+                // page, so reserve it there.
+                slug = reserve_slug(slug);
+                std::path::PathBuf::from(format!("{}.html", slug))
+            }
+        };
+        page_info.slug = slug.clone();
+        let output_file_path = Path::new(output_dir).join(&relative_path);
+        page_info.path = relative_path.to_string_lossy().replace('\\', "/");
+
+        // Copy colocated assets next to the rendered page, preserving layout.
+        // The copy happens once per source directory, while the page's `assets`
+        // list is scoped to the siblings this page actually references.
+        let mut assets: Vec<String> = Vec::new();
+        if let Some(source_dir) = file_path.parent() {
+            let output_parent = output_file_path.parent().unwrap_or(Path::new(output_dir));
+            let copy_this_dir = copied_dirs.insert(source_dir.to_path_buf());
+            for asset in find_related_assets(source_dir)? {
+                if let Some(name) = asset.file_name() {
+                    let asset_output = output_parent.join(name);
+                    if copy_this_dir {
+                        fs::create_dir_all(output_parent)?;
+                        fs::copy(&asset, &asset_output)?;
+                    }
+                    if content.contains(&*name.to_string_lossy()) {
+                        let asset_relative = asset_output
+                            .strip_prefix(output_dir)
+                            .unwrap_or(&asset_output)
+                            .to_string_lossy()
+                            .replace('\\', "/");
+                        assets.push(asset_relative);
+                    }
+                }
+            }
+        }
+
+        rendered.push(RenderedPage { page_info, html_content, output_file_path, assets });
     }
 
+    // Sort newest-first so the home page and post navigation read chronologically.
+    rendered.sort_by(|a, b| sortable_date(&b.page_info.date).cmp(&sortable_date(&a.page_info.date)));
+
+    for i in 0..rendered.len() {
+        let prev = if i + 1 < rendered.len() { Some(&rendered[i + 1].page_info) } else { None };
+        let next = if i > 0 { Some(&rendered[i - 1].page_info) } else { None };
+        let page = &rendered[i];
+        let rendered_content = render_template(tera, &page.html_content, &page.page_info, prev, next, &page.assets, &config).unwrap();
+        if let Some(parent) = page.output_file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_output_file(&page.output_file_path, &rendered_content)?;
+    }
+
+    let pages: Vec<PageInfo> = rendered.into_iter().map(|r| r.page_info).collect();
+
     generate_home_page(&output_dir, tera, &pages, &config)?;
+    generate_taxonomies(&output_dir, tera, &pages, &config)?;
 
     Ok(())
 }
 
+struct RenderedPage {
+    page_info: PageInfo,
+    html_content: String,
+    output_file_path: std::path::PathBuf,
+    assets: Vec<String>,
+}
+
+/// `index` is reserved for the generated home page and taxonomy overview, so a
+/// user slug that collides with it is namespaced to avoid clobbering those.
+fn reserve_slug(slug: String) -> String {
+    if slug == "index" {
+        "index-page".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Slugify a human-readable string into a filesystem- and URL-safe slug:
+/// lowercase, ASCII-folded, with runs of non-alphanumeric characters collapsed
+/// to single hyphens.
+fn slugify(input: &str) -> String {
+    let mut slug = String::new();
+    let mut prev_hyphen = false;
+    for c in input.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+            prev_hyphen = false;
+        } else if !prev_hyphen && !slug.is_empty() {
+            slug.push('-');
+            prev_hyphen = true;
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+#[derive(Serialize)]
+struct TagSummary {
+    name: String,
+    slug: String,
+    count: usize,
+}
+
+fn generate_taxonomies(output_dir: &str, tera: &Tera, pages: &[PageInfo], config: &SiteConfig) -> std::io::Result<()> {
+    let mut tags: HashMap<String, Vec<&PageInfo>> = HashMap::new();
+    for page in pages {
+        for tag in &page.tags {
+            tags.entry(tag.clone()).or_default().push(page);
+        }
+    }
+
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let tags_dir = Path::new(output_dir).join("tags");
+    fs::create_dir_all(&tags_dir)?;
+
+    let base = config.base_url.trim_end_matches('/');
+
+    let mut summaries: Vec<TagSummary> = Vec::new();
+    for (tag, tagged_pages) in &tags {
+        // Reserve `index` so a tag slugifying to it can't overwrite the
+        // generated `tags/index.html` overview.
+        let slug = reserve_slug(slugify(tag));
+
+        // Tag pages live under `tags/`, so rewrite each post link to be
+        // site-absolute (prefixed with `base_url`) rather than relative to the
+        // output root, otherwise links resolve under `tags/` and 404.
+        let linked_pages: Vec<PageInfo> = tagged_pages
+            .iter()
+            .map(|page| {
+                let mut page = (*page).clone();
+                page.path = format!("{}/{}", base, page.path);
+                page
+            })
+            .collect();
+
+        let mut context = Context::new();
+        context.insert("tag", tag);
+        context.insert("pages", &linked_pages);
+        context.insert("site_title", &config.site_title);
+        context.insert("base_url", &config.base_url);
+
+        let rendered = tera.render("tags.html", &context).unwrap();
+        let output_file_path = tags_dir.join(format!("{}.html", slug));
+        write_output_file(&output_file_path, &rendered)?;
+
+        summaries.push(TagSummary {
+            name: tag.clone(),
+            slug,
+            count: tagged_pages.len(),
+        });
+    }
+
+    summaries.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+
+    let mut context = Context::new();
+    context.insert("tags", &summaries);
+    context.insert("site_title", &config.site_title);
+    context.insert("base_url", &config.base_url);
+    let rendered = tera.render("tags/index.html", &context).unwrap();
+    write_output_file(&tags_dir.join("index.html"), &rendered)
+}
+
 fn generate_home_page(output_dir: &str, tera: &Tera, pages: &[PageInfo], config: &SiteConfig) -> std::io::Result<()> {
     let mut context = Context::new();
     context.insert("site_title", &config.site_title);
@@ -119,7 +434,7 @@ fn collect_metadata(content: &str) -> std::io::Result<PageInfo> {
     match result {
         Ok(data) => {
             let page_info = match data.metadata {
-                PageInfo {title, description, tags, similar_posts, date, favorite_numbers, path} => {
+                PageInfo {title, description, tags, similar_posts, date, favorite_numbers, path, slug, ..} => {
                     PageInfo {
                         title,
                         description,
@@ -128,6 +443,10 @@ fn collect_metadata(content: &str) -> std::io::Result<PageInfo> {
                         date,
                         favorite_numbers,
                         path: "".to_string(),
+                        word_count: 0,
+                        reading_time: 0,
+                        summary: "".to_string(),
+                        slug,
                     }
                 },
             };
@@ -137,9 +456,53 @@ fn collect_metadata(content: &str) -> std::io::Result<PageInfo> {
     }
 }
 
+/// Build the Tera instance for a site. When a theme is configured its templates
+/// are loaded first and then overridden by any project-local `templates/**/*`,
+/// so projects can selectively replace theme templates (project wins on name
+/// collision).
+fn load_templates(config: &SiteConfig) -> Result<Tera, tera::Error> {
+    if config.theme.trim().is_empty() {
+        return Tera::new("templates/**/*");
+    }
+
+    let theme_glob = format!("themes/{}/templates/**/*", config.theme);
+    let theme = Tera::new(&theme_glob)?;
+
+    if !Path::new("templates").exists() {
+        return Ok(theme);
+    }
+
+    // `Tera::extend` only adds templates whose names are not already present,
+    // so start from the project templates and fill in the rest from the theme.
+    let mut tera = Tera::new("templates/**/*")?;
+    tera.extend(&theme)?;
+
+    Ok(tera)
+}
+
+/// Recursively copy a directory tree, creating destination directories as
+/// needed.
+fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let path = entry.path();
+        let target = dst.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir_all(&path, &target)?;
+        } else {
+            fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn Error>>{
-    // Initialize Tera for template rendering
-    let tera = match Tera::new("templates/**/*") {
+    let config = load_config()?;
+
+    // Initialize Tera for template rendering, layering project templates over
+    // the configured theme.
+    let tera = match load_templates(&config) {
         Ok(t) => t,
         Err(e) => {
             println!("Parsing error(s): {}", e);
@@ -147,8 +510,6 @@ fn main() -> Result<(), Box<dyn Error>>{
         }
     };
 
-    let config = load_config()?;
-
     let input_dir = format!("./{}", &config.content_location);
     let output_dir = format!("./{}", &config.output_location);
 
@@ -203,4 +564,49 @@ mod tests {
 
         assert_eq!(html_output, "<p><a href=\"https://www.rust-lang.org/\">Rust website</a></p>\n");
     }
+
+    #[test]
+    fn test_derive_date_from_filename() {
+        assert_eq!(
+            derive_date_from_filename(Path::new("content/2024-01-02-my-post.md")),
+            Some("2024-01-02".to_string())
+        );
+        assert_eq!(derive_date_from_filename(Path::new("content/my-post.md")), None);
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Rust Lang"), "rust-lang");
+        assert_eq!(slugify("  Hello, World!  "), "hello-world");
+        assert_eq!(slugify("C++ & Rust"), "c-rust");
+    }
+
+    #[test]
+    fn test_extract_summary_more_marker() {
+        let content = "Intro paragraph.\n\n<!-- more -->\n\nRest of the post.";
+        assert_eq!(extract_summary(content), "<p>Intro paragraph.</p>\n");
+    }
+
+    #[test]
+    fn test_extract_summary_first_paragraph() {
+        let content = "First paragraph here.\n\nSecond paragraph.";
+        assert_eq!(extract_summary(content), "<p>First paragraph here.</p>\n");
+    }
+
+    #[test]
+    fn test_count_words_latin() {
+        assert_eq!(count_words("the quick brown fox"), 4);
+    }
+
+    #[test]
+    fn test_count_words_cjk() {
+        assert_eq!(count_words("你好世界"), 4);
+    }
+
+    #[test]
+    fn test_reading_time_rounds_up_to_one() {
+        assert_eq!(reading_time(0), 1);
+        assert_eq!(reading_time(50), 1);
+        assert_eq!(reading_time(400), 2);
+    }
 }